@@ -1178,6 +1178,10 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
           "in addition to `.mir` files, create graphviz `.dot` files"),
     dump_mir_exclude_pass_number: bool = (false, parse_bool, [UNTRACKED],
           "if set, exclude the pass number when dumping MIR (used in tests)"),
+    dump_hair: Option<String> = (None, parse_opt_string, [UNTRACKED],
+          "print the HAIR mirrored for the matching bodies during MIR construction"),
+    emit_hair: Option<String> = (None, parse_opt_string, [UNTRACKED],
+          "write the HAIR of each body into the output directory, in the given format (`json`)"),
     mir_emit_validate: usize = (0, parse_uint, [TRACKED],
           "emit Validate MIR statements, interpreted e.g. by miri (0: do not emit; 1: if function \
            contains unsafe block, only validate arguments; 2: always emit full validation)"),
@@ -1865,6 +1869,14 @@ pub fn build_session_options_and_crate_config(matches: &getopts::Matches)
         },
     };
 
+    match debugging_opts.emit_hair.as_ref().map(|s| &s[..]) {
+        None | Some("json") => {}
+        Some(format) => {
+            early_error(error_format, &format!("unknown HAIR format `{}`, expected `json`",
+                                               format))
+        }
+    }
+
     if !cg.remark.is_empty() && debuginfo == NoDebugInfo {
         early_warn(error_format, "-C remark will not show source locations without \
                                 --debuginfo");
@@ -2797,6 +2809,10 @@ mod tests {
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
         opts.debugging_opts.dump_mir_graphviz = true;
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.dump_hair = Some(String::from("abc"));
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.emit_hair = Some(String::from("json"));
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
 
         // Make sure changing a [TRACKED] option changes the hash
         opts = reference.clone();
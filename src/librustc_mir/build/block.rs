@@ -30,7 +30,7 @@ impl<'a, 'gcx, 'tcx> Builder<'a, 'gcx, 'tcx> {
             targeted_by_break,
             safety_mode
         } =
-            self.hir.mirror(ast_block);
+            self.hir.mirror_block(ast_block);
         self.in_opt_scope(opt_destruction_scope.map(|de|(de, source_info)), block, move |this| {
             this.in_scope((region_scope, source_info), LintLevel::Inherited, block, move |this| {
                 if targeted_by_break {
@@ -111,7 +111,7 @@ impl<'a, 'gcx, 'tcx> Builder<'a, 'gcx, 'tcx> {
 
                     // Declare the bindings, which may create a visibility scope.
                     let remainder_span = remainder_scope.span(this.hir.tcx(),
-                                                              &this.hir.region_scope_tree);
+                                                              this.hir.scope_tree());
                     let scope = this.declare_bindings(None, remainder_span, lint_level, &pattern);
 
                     // Evaluate the initializer, if present.
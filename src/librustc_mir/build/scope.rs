@@ -696,7 +696,7 @@ impl<'a, 'gcx, 'tcx> Builder<'a, 'gcx, 'tcx> {
                     scope.needs_cleanup = true;
                 }
                 let region_scope_span = region_scope.span(self.hir.tcx(),
-                                                          &self.hir.region_scope_tree);
+                                                          self.hir.scope_tree());
                 // Attribute scope exit drops to scope's closing brace
                 let scope_end = region_scope_span.with_lo(region_scope_span.hi());
                 scope.drops.push(DropData {
@@ -1778,4 +1778,5 @@ register_diagnostics! {
     E0594, // cannot assign to {}
     E0598, // lifetime of {} is too short to guarantee its contents can be...
     E0625, // thread-local statics cannot be accessed at compile-time
+    E0689, // argument is required to be a constant
 }
@@ -16,6 +16,7 @@ use hair::cx::block;
 use hair::cx::to_ref::ToRef;
 use rustc::hir::def::{Def, CtorKind};
 use rustc::middle::const_val::ConstVal;
+use rustc::traits;
 use rustc::ty::{self, AdtKind, VariantDef, Ty};
 use rustc::ty::adjustment::{Adjustment, Adjust, AutoBorrow};
 use rustc::ty::cast::CastKind as TyCastKind;
@@ -26,7 +27,7 @@ impl<'tcx> Mirror<'tcx> for &'tcx hir::Expr {
     type Output = Expr<'tcx>;
 
     fn make_mirror<'a, 'gcx>(self, cx: &mut Cx<'a, 'gcx, 'tcx>) -> Expr<'tcx> {
-        let temp_lifetime = cx.region_scope_tree.temporary_scope(self.hir_id.local_id);
+        let temp_lifetime = cx.scope_tree().temporary_scope(self.hir_id.local_id);
         let expr_scope = region::Scope::Node(self.hir_id.local_id);
 
         debug!("Expr::make_mirror(): id={}, span={:?}", self.id, self.span);
@@ -34,7 +35,7 @@ impl<'tcx> Mirror<'tcx> for &'tcx hir::Expr {
         let mut expr = make_mirror_unadjusted(cx, self);
 
         // Now apply adjustments, if any.
-        for adjustment in cx.tables().expr_adjustments(self) {
+        for adjustment in cx.adjustments(self) {
             debug!("make_mirror: expr={:?} applying adjustment={:?}",
                    expr,
                    adjustment);
@@ -54,19 +55,20 @@ impl<'tcx> Mirror<'tcx> for &'tcx hir::Expr {
         };
 
         // Finally, create a destruction scope, if any.
-        if let Some(region_scope) =
-            cx.region_scope_tree.opt_destruction_scope(self.hir_id.local_id) {
-                expr = Expr {
-                    temp_lifetime,
-                    ty: expr.ty,
-                    span: self.span,
-                    kind: ExprKind::Scope {
-                        region_scope,
-                        value: expr.to_ref(),
-                        lint_level: LintLevel::Inherited,
-                    },
-                };
-            }
+        if let Some(region_scope) = cx.destruction_scope(expr_scope) {
+            expr = Expr {
+                temp_lifetime,
+                ty: expr.ty,
+                span: self.span,
+                kind: ExprKind::Scope {
+                    region_scope,
+                    value: expr.to_ref(),
+                    lint_level: LintLevel::Inherited,
+                },
+            };
+        }
+
+        cx.dump_mirrored(self.id, &expr);
 
         // OK, all done!
         expr
@@ -81,6 +83,13 @@ fn apply_adjustment<'a, 'gcx, 'tcx>(cx: &mut Cx<'a, 'gcx, 'tcx>,
     let Expr { temp_lifetime, span, .. } = expr;
     let kind = match adjustment.kind {
         Adjust::ReifyFnPointer => {
+            match expr.ty.sty {
+                ty::TyFnDef(def_id, substs) => {
+                    debug_assert_eq!(cx.fn_ptr_ty(def_id, substs), adjustment.target,
+                                     "reifying `{:?}` to an unexpected type", expr.ty);
+                }
+                _ => span_bug!(span, "reifying non-fn-item `{:?}`", expr.ty),
+            }
             ExprKind::ReifyFnPointer { source: expr.to_ref() }
         }
         Adjust::UnsafeFnPointer => {
@@ -90,7 +99,7 @@ fn apply_adjustment<'a, 'gcx, 'tcx>(cx: &mut Cx<'a, 'gcx, 'tcx>,
             ExprKind::ClosureFnPointer { source: expr.to_ref() }
         }
         Adjust::NeverToAny => {
-            ExprKind::NeverToAny { source: expr.to_ref() }
+            return cx.never_to_any(expr, adjustment.target);
         }
         Adjust::MutToConstPointer => {
             ExprKind::Cast { source: expr.to_ref() }
@@ -99,15 +108,11 @@ fn apply_adjustment<'a, 'gcx, 'tcx>(cx: &mut Cx<'a, 'gcx, 'tcx>,
             ExprKind::Deref { arg: expr.to_ref() }
         }
         Adjust::Deref(Some(deref)) => {
-            let call = deref.method_call(cx.tcx, expr.ty);
+            let call = cx.deref_method(expr.ty, deref.mutbl, span);
 
             expr = Expr {
                 temp_lifetime,
-                ty: cx.tcx.mk_ref(deref.region,
-                                  ty::TypeAndMut {
-                                    ty: expr.ty,
-                                    mutbl: deref.mutbl,
-                                  }),
+                ty: cx.mk_ref_ty(deref.region, expr.ty, deref.mutbl),
                 span,
                 kind: ExprKind::Borrow {
                     region: deref.region,
@@ -133,11 +138,7 @@ fn apply_adjustment<'a, 'gcx, 'tcx>(cx: &mut Cx<'a, 'gcx, 'tcx>,
             let region = cx.tcx.mk_region(region);
             expr = Expr {
                 temp_lifetime,
-                ty: cx.tcx.mk_ref(region,
-                                  ty::TypeAndMut {
-                                    ty: expr.ty,
-                                    mutbl: m,
-                                  }),
+                ty: cx.mk_ref_ty(region, expr.ty, m),
                 span,
                 kind: ExprKind::Borrow {
                     region,
@@ -164,7 +165,7 @@ fn make_mirror_unadjusted<'a, 'gcx, 'tcx>(cx: &mut Cx<'a, 'gcx, 'tcx>,
                                           expr: &'tcx hir::Expr)
                                           -> Expr<'tcx> {
     let expr_ty = cx.tables().expr_ty(expr);
-    let temp_lifetime = cx.region_scope_tree.temporary_scope(expr.hir_id.local_id);
+    let temp_lifetime = cx.scope_tree().temporary_scope(expr.hir_id.local_id);
 
     let kind = match expr.node {
         // Here comes the interesting stuff:
@@ -211,7 +212,7 @@ fn make_mirror_unadjusted<'a, 'gcx, 'tcx>(cx: &mut Cx<'a, 'gcx, 'tcx>,
                     expr_ty.ty_adt_def().and_then(|adt_def| {
                         match path.def {
                             Def::VariantCtor(variant_id, CtorKind::Fn) => {
-                                Some((adt_def, adt_def.variant_index_with_id(variant_id)))
+                                Some((adt_def, cx.variant_index_of(adt_def, variant_id)))
                             }
                             Def::StructCtor(_, CtorKind::Fn) => Some((adt_def, 0)),
                             _ => None,
@@ -239,10 +240,11 @@ fn make_mirror_unadjusted<'a, 'gcx, 'tcx>(cx: &mut Cx<'a, 'gcx, 'tcx>,
                         base: None,
                     }
                 } else {
+                    let fun_ty = cx.tables().node_id_to_type(fun.hir_id);
                     ExprKind::Call {
-                        ty: cx.tables().node_id_to_type(fun.hir_id),
+                        ty: fun_ty,
                         fun: fun.to_ref(),
-                        args: args.to_ref(),
+                        args: call_args(cx, fun_ty, args),
                     }
                 }
             }
@@ -281,14 +283,16 @@ fn make_mirror_unadjusted<'a, 'gcx, 'tcx>(cx: &mut Cx<'a, 'gcx, 'tcx>,
             }
         }
 
-        hir::ExprLit(..) => ExprKind::Literal { literal: cx.const_eval_literal(expr) },
+        hir::ExprLit(ref lit) => ExprKind::Literal {
+            literal: cx.literal_from_hir_lit(lit, expr_ty, false),
+        },
 
         hir::ExprBinary(op, ref lhs, ref rhs) => {
             if cx.tables().is_method_call(expr) {
                 overloaded_operator(cx, expr, vec![lhs.to_ref(), rhs.to_ref()])
             } else {
                 // FIXME overflow
-                match (op.node, cx.constness) {
+                match (op.node, cx.constness()) {
                     // FIXME(eddyb) use logical ops in constants when
                     // they can handle that kind of control-flow.
                     (hir::BinOp_::BiAnd, hir::Constness::Const) => {
@@ -368,8 +372,8 @@ fn make_mirror_unadjusted<'a, 'gcx, 'tcx>(cx: &mut Cx<'a, 'gcx, 'tcx>,
                 overloaded_operator(cx, expr, vec![arg.to_ref()])
             } else {
                 // FIXME runtime-overflow
-                if let hir::ExprLit(_) = arg.node {
-                    ExprKind::Literal { literal: cx.const_eval_literal(expr) }
+                if let hir::ExprLit(ref lit) = arg.node {
+                    ExprKind::Literal { literal: cx.literal_from_hir_lit(lit, expr_ty, true) }
                 } else {
                     ExprKind::Unary {
                         op: UnOp::Neg,
@@ -409,7 +413,7 @@ fn make_mirror_unadjusted<'a, 'gcx, 'tcx>(cx: &mut Cx<'a, 'gcx, 'tcx>,
                                 Def::Variant(variant_id) => {
                                     assert!(base.is_none());
 
-                                    let index = adt.variant_index_with_id(variant_id);
+                                    let index = cx.variant_index_of(adt, variant_id);
                                     let field_refs = field_refs(&adt.variants[index], fields);
                                     ExprKind::Adt {
                                         adt_def: adt,
@@ -443,10 +447,11 @@ fn make_mirror_unadjusted<'a, 'gcx, 'tcx>(cx: &mut Cx<'a, 'gcx, 'tcx>,
                     span_bug!(expr.span, "closure expr w/o closure type: {:?}", closure_ty);
                 }
             };
+            let captures = cx.closure_captures(def_id);
             let upvars = cx.tcx.with_freevars(expr.id, |freevars| {
                 freevars.iter()
-                    .zip(substs.upvar_tys(def_id, cx.tcx))
-                    .map(|(fv, ty)| capture_freevar(cx, expr, fv, ty))
+                    .zip(captures)
+                    .map(|(fv, capture)| capture_freevar(cx, expr, fv, capture))
                     .collect()
             });
             ExprKind::Closure {
@@ -475,10 +480,15 @@ fn make_mirror_unadjusted<'a, 'gcx, 'tcx>(cx: &mut Cx<'a, 'gcx, 'tcx>,
             let c = &cx.tcx.hir.body(count).value;
             let def_id = cx.tcx.hir.body_owner_def_id(count);
             let substs = Substs::identity_for_item(cx.tcx.global_tcx(), def_id);
-            let count = match cx.tcx.at(c.span).const_eval(cx.param_env.and((def_id, substs))) {
-                Ok(&ty::Const { val: ConstVal::Integral(ConstInt::Usize(u)), .. }) => u,
-                Ok(other) => bug!("constant evaluation of repeat count yielded {:?}", other),
-                Err(s) => cx.fatal_const_eval_err(&s, c.span, "expression")
+            // Typeck evaluates the count outside of the enclosing body's
+            // environment, so do the same here.
+            let param_env = ty::ParamEnv::empty(traits::Reveal::UserFacing);
+            let count = cx.with_param_env(param_env, |cx| {
+                cx.eval_const_arg(def_id, substs, c.span)
+            });
+            let count = match count {
+                ConstVal::Integral(ConstInt::Usize(u)) => u,
+                other => bug!("constant evaluation of repeat count yielded {:?}", other),
             };
 
             ExprKind::Repeat {
@@ -574,7 +584,12 @@ fn make_mirror_unadjusted<'a, 'gcx, 'tcx>(cx: &mut Cx<'a, 'gcx, 'tcx>,
         hir::ExprArray(ref fields) => ExprKind::Array { fields: fields.to_ref() },
         hir::ExprTup(ref fields) => ExprKind::Tuple { fields: fields.to_ref() },
 
-        hir::ExprYield(ref v) => ExprKind::Yield { value: v.to_ref() },
+        hir::ExprYield(ref v) => {
+            if !cx.is_generator() {
+                span_bug!(expr.span, "`yield` outside of a generator body");
+            }
+            ExprKind::Yield { value: v.to_ref() }
+        }
     };
 
     Expr {
@@ -587,26 +602,18 @@ fn make_mirror_unadjusted<'a, 'gcx, 'tcx>(cx: &mut Cx<'a, 'gcx, 'tcx>,
 
 fn method_callee<'a, 'gcx, 'tcx>(cx: &mut Cx<'a, 'gcx, 'tcx>,
                                  expr: &hir::Expr,
-                                 custom_callee: Option<(DefId, &'tcx Substs<'tcx>)>)
+                                 custom_callee: Option<(Ty<'tcx>, Literal<'tcx>)>)
                                  -> Expr<'tcx> {
-    let temp_lifetime = cx.region_scope_tree.temporary_scope(expr.hir_id.local_id);
-    let (def_id, substs) = custom_callee.unwrap_or_else(|| {
-        (cx.tables().type_dependent_defs()[expr.hir_id].def_id(),
-         cx.tables().node_substs(expr.hir_id))
-    });
-    let ty = cx.tcx().mk_fn_def(def_id, substs);
+    let temp_lifetime = cx.scope_tree().temporary_scope(expr.hir_id.local_id);
+    let (ty, literal) = match custom_callee {
+        Some(callee) => callee,
+        None => cx.method_callee(expr, expr.span),
+    };
     Expr {
         temp_lifetime,
         ty,
         span: expr.span,
-        kind: ExprKind::Literal {
-            literal: Literal::Value {
-                value: cx.tcx.mk_const(ty::Const {
-                    val: ConstVal::Function(def_id, substs),
-                    ty
-                }),
-            },
-        },
+        kind: ExprKind::Literal { literal },
     }
 }
 
@@ -664,7 +671,7 @@ fn convert_path_expr<'a, 'gcx, 'tcx>(cx: &mut Cx<'a, 'gcx, 'tcx>,
                 ty::TyAdt(adt_def, substs) => {
                     ExprKind::Adt {
                         adt_def,
-                        variant_index: adt_def.variant_index_with_id(def_id),
+                        variant_index: cx.variant_index_of(adt_def, def_id),
                         substs,
                         fields: vec![],
                         base: None,
@@ -686,7 +693,7 @@ fn convert_var<'a, 'gcx, 'tcx>(cx: &mut Cx<'a, 'gcx, 'tcx>,
                                expr: &'tcx hir::Expr,
                                def: Def)
                                -> ExprKind<'tcx> {
-    let temp_lifetime = cx.region_scope_tree.temporary_scope(expr.hir_id.local_id);
+    let temp_lifetime = cx.scope_tree().temporary_scope(expr.hir_id.local_id);
 
     match def {
         Def::Local(id) => ExprKind::VarRef { id },
@@ -847,7 +854,7 @@ fn overloaded_operator<'a, 'gcx, 'tcx>(cx: &mut Cx<'a, 'gcx, 'tcx>,
 fn overloaded_place<'a, 'gcx, 'tcx>(cx: &mut Cx<'a, 'gcx, 'tcx>,
                                      expr: &'tcx hir::Expr,
                                      place_ty: Ty<'tcx>,
-                                     custom_callee: Option<(DefId, &'tcx Substs<'tcx>)>,
+                                     custom_callee: Option<(Ty<'tcx>, Literal<'tcx>)>,
                                      args: Vec<ExprRef<'tcx>>)
                                      -> ExprKind<'tcx> {
     // For an overloaded *x or x[y] expression of type T, the method
@@ -866,14 +873,11 @@ fn overloaded_place<'a, 'gcx, 'tcx>(cx: &mut Cx<'a, 'gcx, 'tcx>,
         ty::TyRef(region, mt) => (region, mt),
         _ => span_bug!(expr.span, "overloaded_place: receiver is not a reference"),
     };
-    let ref_ty = cx.tcx.mk_ref(region, ty::TypeAndMut {
-        ty: place_ty,
-        mutbl: mt.mutbl,
-    });
+    let ref_ty = cx.mk_ref_ty(region, place_ty, mt.mutbl);
 
     // construct the complete expression `foo()` for the overloaded call,
     // which will yield the &T type
-    let temp_lifetime = cx.region_scope_tree.temporary_scope(expr.hir_id.local_id);
+    let temp_lifetime = cx.scope_tree().temporary_scope(expr.hir_id.local_id);
     let fun = method_callee(cx, expr, custom_callee);
     let ref_expr = Expr {
         temp_lifetime,
@@ -890,18 +894,45 @@ fn overloaded_place<'a, 'gcx, 'tcx>(cx: &mut Cx<'a, 'gcx, 'tcx>,
     ExprKind::Deref { arg: ref_expr.to_ref() }
 }
 
+/// The arguments of a call to a `fun_ty`, with those the callee requires
+/// to be constants (see `Cx::require_const_arg`) already evaluated.
+fn call_args<'a, 'gcx, 'tcx>(cx: &mut Cx<'a, 'gcx, 'tcx>,
+                             fun_ty: Ty<'tcx>,
+                             args: &'tcx hir::HirVec<hir::Expr>)
+                             -> Vec<ExprRef<'tcx>> {
+    let callee = match fun_ty.sty {
+        ty::TyFnDef(def_id, _) => def_id,
+        _ => return args.to_ref(),
+    };
+    args.iter().enumerate().map(|(index, arg)| {
+        match cx.require_const_arg(callee, index, arg) {
+            Some(literal) => {
+                // The constant has the type of `arg` itself, so it still
+                // needs the coercions typeck recorded for the argument.
+                let mut expr = Expr {
+                    temp_lifetime: cx.scope_tree().temporary_scope(arg.hir_id.local_id),
+                    ty: cx.tables().expr_ty(arg),
+                    span: arg.span,
+                    kind: ExprKind::Literal { literal },
+                };
+                for adjustment in cx.adjustments(arg) {
+                    expr = apply_adjustment(cx, arg, expr, adjustment);
+                }
+                debug_assert_eq!(expr.ty, cx.tables().expr_ty_adjusted(arg));
+                expr.to_ref()
+            }
+            None => arg.to_ref(),
+        }
+    }).collect()
+}
+
 fn capture_freevar<'a, 'gcx, 'tcx>(cx: &mut Cx<'a, 'gcx, 'tcx>,
                                    closure_expr: &'tcx hir::Expr,
                                    freevar: &hir::Freevar,
-                                   freevar_ty: Ty<'tcx>)
+                                   capture: UpvarInfo<'tcx>)
                                    -> ExprRef<'tcx> {
-    let var_hir_id = cx.tcx.hir.node_to_hir_id(freevar.var_id());
-    let upvar_id = ty::UpvarId {
-        var_id: var_hir_id,
-        closure_expr_id: cx.tcx.hir.local_def_id(closure_expr.id).to_local(),
-    };
-    let upvar_capture = cx.tables().upvar_capture(upvar_id);
-    let temp_lifetime = cx.region_scope_tree.temporary_scope(closure_expr.hir_id.local_id);
+    let var_hir_id = capture.upvar_id.var_id;
+    let temp_lifetime = cx.scope_tree().temporary_scope(closure_expr.hir_id.local_id);
     let var_ty = cx.tables().node_id_to_type(var_hir_id);
     let captured_var = Expr {
         temp_lifetime,
@@ -909,7 +940,7 @@ fn capture_freevar<'a, 'gcx, 'tcx>(cx: &mut Cx<'a, 'gcx, 'tcx>,
         span: closure_expr.span,
         kind: convert_var(cx, closure_expr, freevar.def),
     };
-    match upvar_capture {
+    match capture.capture {
         ty::UpvarCapture::ByValue => captured_var.to_ref(),
         ty::UpvarCapture::ByRef(upvar_borrow) => {
             let borrow_kind = match upvar_borrow.kind {
@@ -919,7 +950,7 @@ fn capture_freevar<'a, 'gcx, 'tcx>(cx: &mut Cx<'a, 'gcx, 'tcx>,
             };
             Expr {
                 temp_lifetime,
-                ty: freevar_ty,
+                ty: capture.ty,
                 span: closure_expr.span,
                 kind: ExprKind::Borrow {
                     region: upvar_borrow.region,
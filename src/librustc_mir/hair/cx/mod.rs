@@ -15,21 +15,34 @@
 //!
 
 use hair::*;
+use hair::cx::to_ref::ToRef;
 
-use rustc::middle::const_val::{ConstEvalErr, ConstVal};
+use rustc::middle::const_val::{ByteArray, ConstEvalErr, ConstVal, ErrKind};
 use rustc_const_eval::ConstContext;
+use rustc_data_structures::fx::FxHashMap;
 use rustc_data_structures::indexed_vec::Idx;
 use rustc::hir::def_id::{DefId, LOCAL_CRATE};
 use rustc::hir::map::blocks::FnLikeNode;
+use rustc::lint::LintLevelMap;
+use rustc::middle::lang_items;
+use rustc::mir::AssertMessage;
 use rustc::middle::region;
 use rustc::infer::InferCtxt;
+use rustc::traits;
+use rustc::ty::fold::TypeFoldable;
+use rustc::ty::maps::TyCtxtAt;
 use rustc::ty::subst::Subst;
-use rustc::ty::{self, Ty, TyCtxt};
+use rustc::ty::{self, item_path, Ty, TyCtxt};
 use rustc::ty::subst::Substs;
+use rustc::ty::util::IntTypeExt;
 use syntax::ast;
+use syntax::feature_gate;
 use syntax::symbol::Symbol;
 use rustc::hir;
-use rustc_const_math::{ConstInt, ConstUsize};
+use rustc_const_math::{ConstFloat, ConstInt, ConstMathErr, ConstUsize};
+use std::cell::Ref;
+use std::fmt;
+use std::mem;
 use std::rc::Rc;
 
 #[derive(Clone)]
@@ -43,6 +56,10 @@ pub struct Cx<'a, 'gcx: 'a + 'tcx, 'tcx: 'a> {
     /// Identity `Substs` for use with const-evaluation.
     pub identity_substs: &'gcx Substs<'gcx>,
 
+    /// The scope tree of the body being lowered. Queries hand this out
+    /// as an `Rc` rather than as a borrow of `tcx`, so we have to keep
+    /// it alive ourselves; use `scope_tree()` to read it, and avoid
+    /// cloning the whole `Cx` (which bumps this refcount).
     pub region_scope_tree: Rc<region::ScopeTree>,
     pub tables: &'a ty::TypeckTables<'gcx>,
 
@@ -53,16 +70,56 @@ pub struct Cx<'a, 'gcx: 'a + 'tcx, 'tcx: 'a> {
     /// What kind of body is being compiled.
     pub body_owner_kind: hir::BodyOwnerKind,
 
+    /// True if the body contains `yield`, i.e. is a generator.
+    is_generator: bool,
+
     /// True if this constant/function needs overflow checks.
     check_overflow: bool,
+
+    /// True if `-Z dump-hair` selected this body, in which case every
+    /// mirrored node is printed as it is produced; see `dump_mirrored`.
+    dump_hair: bool,
+
+    /// Methods already resolved by `trait_method`, so that repeated
+    /// lookups of e.g. `PartialEq::eq` don't rescan the trait's items.
+    trait_methods: FxHashMap<(DefId, Symbol), DefId>,
+
+    /// The `false` and `true` constants, in that order, once interned by
+    /// `bool_const`.
+    bool_consts: [Option<&'tcx ty::Const<'tcx>>; 2],
+
+    /// The arguments each callee seen so far requires to be constant;
+    /// see `required_const_args`.
+    required_const_args: FxHashMap<DefId, Vec<usize>>,
+
+    /// The crate's lint levels, fetched once so that `lint_level_of`,
+    /// which runs for every mirrored expression and local, doesn't go
+    /// through the query each time.
+    lint_levels: Rc<LintLevelMap>,
 }
 
 impl<'a, 'gcx, 'tcx> Cx<'a, 'gcx, 'tcx> {
     pub fn new(infcx: &'a InferCtxt<'a, 'gcx, 'tcx>,
                src_id: ast::NodeId) -> Cx<'a, 'gcx, 'tcx> {
+        let src_def_id = infcx.tcx.hir.local_def_id(src_id);
+        let region_scope_tree = infcx.tcx.region_scope_tree(src_def_id);
+        Cx::new_with_scope_tree(infcx, src_id, region_scope_tree)
+    }
+
+    /// Like `new`, but uses `region_scope_tree` instead of querying it, for
+    /// drivers that lower several bodies sharing one scope tree (e.g. an
+    /// item and its closures). The tree must be `region_scope_tree(src_id)`.
+    pub fn new_with_scope_tree(infcx: &'a InferCtxt<'a, 'gcx, 'tcx>,
+                               src_id: ast::NodeId,
+                               region_scope_tree: Rc<region::ScopeTree>)
+                               -> Cx<'a, 'gcx, 'tcx> {
         let tcx = infcx.tcx;
         let src_def_id = tcx.hir.local_def_id(src_id);
+        debug_assert!(Rc::ptr_eq(&region_scope_tree, &tcx.region_scope_tree(src_def_id)),
+                      "Cx::new_with_scope_tree: scope tree does not belong to {:?}",
+                      src_def_id);
         let body_owner_kind = tcx.hir.body_owner_kind(src_id);
+        let is_generator = tcx.hir.body(tcx.hir.body_owned_by(src_id)).is_generator;
 
         let constness = match body_owner_kind {
             hir::BodyOwnerKind::Const |
@@ -87,18 +144,34 @@ impl<'a, 'gcx, 'tcx> Cx<'a, 'gcx, 'tcx> {
         // Constants and const fn's always need overflow checks.
         check_overflow |= constness == hir::Constness::Const;
 
+        let dump_hair = tcx.sess.opts.debugging_opts.dump_hair.as_ref().map_or(false, |filters| {
+            // see notes on #41697 in `util::pretty`
+            let node_path = item_path::with_forced_impl_filename_line(|| {
+                tcx.item_path_str(src_def_id)
+            });
+            filters.split("|").any(|filter| filter == "all" || node_path.contains(filter))
+        });
+
         let lint_level = lint_level_for_hir_id(tcx, src_id);
+        // See the comment in `lint_level_for_hir_id` on `with_ignore`.
+        let lint_levels = tcx.dep_graph.with_ignore(|| tcx.lint_levels(LOCAL_CRATE));
         Cx {
             tcx,
             infcx,
             root_lint_level: lint_level,
             param_env: tcx.param_env(src_def_id),
             identity_substs: Substs::identity_for_item(tcx.global_tcx(), src_def_id),
-            region_scope_tree: tcx.region_scope_tree(src_def_id),
+            region_scope_tree,
             tables: tcx.typeck_tables_of(src_def_id),
             constness,
             body_owner_kind,
+            is_generator,
             check_overflow,
+            dump_hair,
+            trait_methods: FxHashMap(),
+            bool_consts: [None, None],
+            required_const_args: FxHashMap(),
+            lint_levels,
         }
     }
 
@@ -110,6 +183,44 @@ impl<'a, 'gcx, 'tcx> Cx<'a, 'gcx, 'tcx> {
         ast.make_mirror(self)
     }
 
+    /// Prints `node`, just mirrored from the HIR node `id`, if `-Z dump-hair`
+    /// selected this body. Each HIR expression and block is mirrored once,
+    /// so this is called from their `make_mirror`s; children that are not
+    /// mirrored yet show up as `Hair(expr(<id>: ...))` and are printed
+    /// under their own id once they are.
+    fn dump_mirrored<T: fmt::Debug>(&self, id: ast::NodeId, node: &T) {
+        if self.dump_hair {
+            println!("{} => {:#?}", id, node);
+        }
+    }
+
+    /// Mirrors a single expression, exactly as the builder does when it
+    /// lowers one. The result is wrapped in the expression's region and
+    /// lint-level scopes, and overflow checks follow `check_overflow()`.
+    pub fn mirror_expr(&mut self, expr: &'tcx hir::Expr) -> Expr<'tcx> {
+        self.mirror(expr)
+    }
+
+    /// Mirrors a single block, exactly as the builder does when it lowers
+    /// one: the statements are mirrored eagerly and in source order (so
+    /// that `let` remainder scopes nest correctly), while the trailing
+    /// expression, if any, is left to be mirrored on demand.
+    pub fn mirror_block(&mut self, block: &'tcx hir::Block) -> Block<'tcx> {
+        self.mirror(block)
+    }
+
+    /// Coerces the diverging expression `expr` to `target_ty`.
+    pub fn never_to_any(&mut self, expr: Expr<'tcx>, target_ty: Ty<'tcx>) -> Expr<'tcx> {
+        debug_assert!(expr.ty.is_never(),
+                      "never_to_any: expression of type `{:?}` does not diverge", expr.ty);
+        Expr {
+            temp_lifetime: expr.temp_lifetime,
+            ty: target_ty,
+            span: expr.span,
+            kind: ExprKind::NeverToAny { source: expr.to_ref() },
+        }
+    }
+
     pub fn usize_ty(&mut self) -> Ty<'tcx> {
         self.tcx.types.usize
     }
@@ -128,48 +239,296 @@ impl<'a, 'gcx, 'tcx> Cx<'a, 'gcx, 'tcx> {
         }
     }
 
+    pub fn int_literal(&mut self, value: i128, ty: Ty<'tcx>) -> Literal<'tcx> {
+        let int_ty = match ty.sty {
+            ty::TyInt(int_ty) => int_ty,
+            _ => bug!("int_literal: `{:?}` is not a signed integer type", ty),
+        };
+        // `ConstInt::new_signed` only checks the upper bound, so check
+        // that the value survives truncation instead.
+        let val = ConstInt::new_signed_truncating(value, int_ty, self.tcx.sess.target.isize_ty);
+        if val.to_u128_unchecked() as i128 != value {
+            bug!("int literal {} out of range for `{:?}`", value, ty);
+        }
+        Literal::Value {
+            value: self.tcx.mk_const(ty::Const {
+                val: ConstVal::Integral(val),
+                ty
+            })
+        }
+    }
+
+    pub fn uint_literal(&mut self, value: u128, ty: Ty<'tcx>) -> Literal<'tcx> {
+        let uint_ty = match ty.sty {
+            ty::TyUint(uint_ty) => uint_ty,
+            _ => bug!("uint_literal: `{:?}` is not an unsigned integer type", ty),
+        };
+        match ConstInt::new_unsigned(value, uint_ty, self.tcx.sess.target.usize_ty) {
+            Some(val) => {
+                Literal::Value {
+                    value: self.tcx.mk_const(ty::Const {
+                        val: ConstVal::Integral(val),
+                        ty
+                    })
+                }
+            }
+            None => bug!("uint literal {} out of range for `{:?}`", value, ty),
+        }
+    }
+
+    /// Builds an `f32` constant from its raw bit pattern, so that NaN
+    /// payloads and the sign of zero are preserved exactly.
+    pub fn f32_literal(&mut self, bits: u32) -> Literal<'tcx> {
+        Literal::Value {
+            value: self.tcx.mk_const(ty::Const {
+                val: ConstVal::Float(ConstFloat {
+                    ty: ast::FloatTy::F32,
+                    bits: bits as u128,
+                }),
+                ty: self.tcx.types.f32
+            })
+        }
+    }
+
+    /// Builds an `f64` constant from its raw bit pattern; see `f32_literal`.
+    pub fn f64_literal(&mut self, bits: u64) -> Literal<'tcx> {
+        Literal::Value {
+            value: self.tcx.mk_const(ty::Const {
+                val: ConstVal::Float(ConstFloat {
+                    ty: ast::FloatTy::F64,
+                    bits: bits as u128,
+                }),
+                ty: self.tcx.types.f64
+            })
+        }
+    }
+
     pub fn bool_ty(&mut self) -> Ty<'tcx> {
         self.tcx.types.bool
     }
 
+    pub fn mk_ref_ty(&self, region: ty::Region<'tcx>, ty: Ty<'tcx>, mutbl: hir::Mutability)
+                     -> Ty<'tcx> {
+        self.tcx.mk_ref(region, ty::TypeAndMut { ty, mutbl })
+    }
+
+    /// The type `[elem_ty; len]`. A `len` that doesn't fit the target's
+    /// `usize` is reported as an error, giving the error type.
+    pub fn mk_array_ty(&self, elem_ty: Ty<'tcx>, len: u64) -> Ty<'tcx> {
+        match ConstUsize::new(len, self.tcx.sess.target.usize_ty) {
+            Ok(len) => self.tcx.mk_array_const_usize(elem_ty, len),
+            Err(_) => {
+                self.tcx.sess.err(&format!("the type `[{}; {}]` is too big for the current \
+                                            architecture", elem_ty, len));
+                self.tcx.types.err
+            }
+        }
+    }
+
+    pub fn mk_tuple_ty(&self, tys: &[Ty<'tcx>]) -> Ty<'tcx> {
+        self.tcx.intern_tup(tys, false)
+    }
+
     pub fn unit_ty(&mut self) -> Ty<'tcx> {
         self.tcx.mk_nil()
     }
 
     pub fn true_literal(&mut self) -> Literal<'tcx> {
+        Literal::Value { value: self.bool_const(true) }
+    }
+
+    pub fn false_literal(&mut self) -> Literal<'tcx> {
+        Literal::Value { value: self.bool_const(false) }
+    }
+
+    /// The interned `bool` constant `value`, looked up once per `Cx`.
+    fn bool_const(&mut self, value: bool) -> &'tcx ty::Const<'tcx> {
+        if let Some(c) = self.bool_consts[value as usize] {
+            return c;
+        }
+        let c = self.tcx.mk_const(ty::Const {
+            val: ConstVal::Bool(value),
+            ty: self.tcx.types.bool
+        });
+        self.bool_consts[value as usize] = Some(c);
+        c
+    }
+
+    pub fn char_literal(&mut self, c: char) -> Literal<'tcx> {
         Literal::Value {
             value: self.tcx.mk_const(ty::Const {
-                val: ConstVal::Bool(true),
-                ty: self.tcx.types.bool
+                val: ConstVal::Char(c),
+                ty: self.tcx.types.char
             })
         }
     }
 
-    pub fn false_literal(&mut self) -> Literal<'tcx> {
+    pub fn str_literal(&mut self, s: &str) -> Literal<'tcx> {
         Literal::Value {
             value: self.tcx.mk_const(ty::Const {
-                val: ConstVal::Bool(false),
-                ty: self.tcx.types.bool
+                val: ConstVal::Str(Symbol::intern(s).as_str()),
+                ty: self.tcx.mk_static_str()
             })
         }
     }
 
-    pub fn const_eval_literal(&mut self, e: &hir::Expr) -> Literal<'tcx> {
+    /// Lowers the literal `lit` of type `ty`, negated if `neg` is set
+    /// (i.e. `lit` is the operand of a unary `-`).
+    ///
+    /// Integer literals are truncated to `ty` like constant evaluation
+    /// does: out-of-range literals were already reported by the
+    /// `overflowing_literals` lint, which may have been allowed.
+    pub fn literal_from_hir_lit(&mut self, lit: &ast::Lit, ty: Ty<'tcx>, neg: bool)
+                                -> Literal<'tcx> {
+        match (&lit.node, neg) {
+            (&ast::LitKind::Int(n, _), _) => {
+                let val = match ty.sty {
+                    ty::TyInt(int_ty) => {
+                        let n = if neg { (n as i128).wrapping_neg() } else { n as i128 };
+                        let isize_ty = self.tcx.sess.target.isize_ty;
+                        ConstInt::new_signed_truncating(n, int_ty, isize_ty)
+                    }
+                    ty::TyUint(uint_ty) if !neg => {
+                        let usize_ty = self.tcx.sess.target.usize_ty;
+                        ConstInt::new_unsigned_truncating(n, uint_ty, usize_ty)
+                    }
+                    _ => {
+                        self.span_bug(lit.span,
+                                      &format!("invalid type for integer literal: `{:?}`", ty))
+                    }
+                };
+                Literal::Value {
+                    value: self.tcx.mk_const(ty::Const {
+                        val: ConstVal::Integral(val),
+                        ty
+                    })
+                }
+            }
+            (&ast::LitKind::Float(n, _), _) |
+            (&ast::LitKind::FloatUnsuffixed(n), _) => {
+                let fty = match ty.sty {
+                    ty::TyFloat(fty) => fty,
+                    _ => {
+                        self.span_bug(lit.span,
+                                      &format!("invalid type for float literal: `{:?}`", ty))
+                    }
+                };
+                let f = match ConstFloat::from_str(&n.as_str(), fty) {
+                    Ok(f) => if neg { -f } else { f },
+                    Err(_) => {
+                        // FIXME(#31407) this is only necessary because float parsing is buggy
+                        let err = ConstEvalErr {
+                            span: lit.span,
+                            kind: ErrKind::UnimplementedConstVal(
+                                "could not evaluate float literal (see issue #31407)"),
+                        };
+                        self.fatal_const_eval_err(&err, lit.span, "expression")
+                    }
+                };
+                match fty {
+                    ast::FloatTy::F32 => self.f32_literal(f.bits as u32),
+                    ast::FloatTy::F64 => self.f64_literal(f.bits as u64),
+                }
+            }
+            (_, true) => {
+                self.span_bug(lit.span, &format!("cannot negate literal `{:?}`", lit.node))
+            }
+            (&ast::LitKind::Str(s, _), false) => self.str_literal(&s.as_str()),
+            (&ast::LitKind::ByteStr(ref data), false) => {
+                Literal::Value {
+                    value: self.tcx.mk_const(ty::Const {
+                        val: ConstVal::ByteStr(ByteArray {
+                            data: self.tcx.alloc_byte_array(data),
+                        }),
+                        ty
+                    })
+                }
+            }
+            (&ast::LitKind::Byte(n), false) => self.uint_literal(n as u128, ty),
+            (&ast::LitKind::Char(c), false) => self.char_literal(c),
+            (&ast::LitKind::Bool(true), false) => self.true_literal(),
+            (&ast::LitKind::Bool(false), false) => self.false_literal(),
+        }
+    }
+
+    /// Evaluates `e` as a constant, handing evaluation errors back to the
+    /// caller instead of reporting them.
+    pub fn try_const_eval_literal(&mut self, e: &hir::Expr)
+                                  -> Result<Literal<'tcx>, ConstEvalErr<'tcx>> {
         let tcx = self.tcx.global_tcx();
+        // Without generics in scope nothing can be specialized further,
+        // so it is sound to see through specializable projections.
+        let param_env = if self.identity_substs.is_empty() {
+            self.param_env_reveal_all()
+        } else {
+            self.param_env
+        };
         let const_cx = ConstContext::new(tcx,
-                                         self.param_env.and(self.identity_substs),
+                                         param_env.and(self.identity_substs),
                                          self.tables());
-        match const_cx.eval(tcx.hir.expect_expr(e.id)) {
-            Ok(value) => Literal::Value { value },
-            Err(s) => self.fatal_const_eval_err(&s, e.span, "expression")
+        const_cx.eval(tcx.hir.expect_expr(e.id)).map(|value| Literal::Value { value })
+    }
+
+    /// Evaluates the constant `def_id` (e.g. an array length or repeat
+    /// count) instantiated with `substs`, aborting on errors.
+    pub fn eval_const_arg(&mut self,
+                          def_id: DefId,
+                          substs: &'tcx Substs<'tcx>,
+                          span: Span)
+                          -> ConstVal<'tcx> {
+        let global_substs = self.tcx.lift_to_global(&substs).unwrap_or_else(|| {
+            self.span_bug(span, &format!("MIR: Cx::eval_const_arg({:?}) got \
+                                          substs with inference types/regions", substs))
+        });
+        match self.tcx_at(span).const_eval(self.param_env.and((def_id, global_substs))) {
+            Ok(&ty::Const { ref val, .. }) => val.clone(),
+            Err(err) => self.fatal_const_eval_err(&err, span, "expression"),
         }
     }
 
+    /// If `callee` is `#[rustc_args_required_const]` at `arg_index`
+    /// (counting from 0), evaluates `arg` to the constant it must be,
+    /// reporting an error and returning `None` if it isn't one. Returns
+    /// `None` for arguments that need not be constant.
+    pub fn require_const_arg(&mut self, callee: DefId, arg_index: usize, arg: &hir::Expr)
+                             -> Option<Literal<'tcx>> {
+        if !self.required_const_args(callee).contains(&arg_index) {
+            return None;
+        }
+        match self.try_const_eval_literal(arg) {
+            Ok(literal) => Some(literal),
+            Err(err) => {
+                let mut diag = struct_span_err!(self.tcx.sess, arg.span, E0689,
+                                                "argument {} is required to be a constant",
+                                                arg_index + 1);
+                err.note(self.tcx, arg.span, "argument", &mut diag);
+                diag.emit();
+                None
+            }
+        }
+    }
+
+    /// The indices listed in `callee`'s `#[rustc_args_required_const]`, if
+    /// any, parsed once per callee.
+    fn required_const_args(&mut self, callee: DefId) -> &[usize] {
+        let tcx = self.tcx;
+        self.required_const_args.entry(callee).or_insert_with(|| {
+            tcx.get_attrs(callee).iter()
+                .filter(|attr| attr.check_name("rustc_args_required_const"))
+                .flat_map(|attr| attr.meta_item_list().unwrap_or(Vec::new()))
+                .filter_map(|item| match item.literal().map(|lit| &lit.node) {
+                    Some(&ast::LitKind::Int(index, _)) => Some(index as usize),
+                    _ => None,
+                })
+                .collect()
+        })
+    }
+
     pub fn pattern_from_hir(&mut self, p: &hir::Pat) -> Pattern<'tcx> {
         let tcx = self.tcx.global_tcx();
         let p = match tcx.hir.get(p.id) {
             hir::map::NodePat(p) | hir::map::NodeBinding(p) => p,
-            node => bug!("pattern became {:?}", node)
+            node => self.span_bug(p.span, &format!("pattern became {:?}", node))
         };
         Pattern::from_hir(tcx,
                           self.param_env.and(self.identity_substs),
@@ -188,29 +547,89 @@ impl<'a, 'gcx, 'tcx> Cx<'a, 'gcx, 'tcx> {
         unreachable!()
     }
 
+    /// Reports an ICE at `span` through the session, so that bugs hit
+    /// while lowering point at the offending source.
+    pub fn span_bug(&self, span: Span, msg: &str) -> ! {
+        self.tcx.sess.diagnostic().span_bug(span, msg)
+    }
+
+    /// Returns the type and callee of `<self_ty as Trait<params>>::method_name`.
+    /// The queries this runs are attributed to `span`, so that a cycle
+    /// hit while resolving the method points at the code being lowered.
     pub fn trait_method(&mut self,
                         trait_def_id: DefId,
                         method_name: &str,
                         self_ty: Ty<'tcx>,
-                        params: &[Ty<'tcx>])
+                        params: &[Ty<'tcx>],
+                        span: Span)
                         -> (Ty<'tcx>, Literal<'tcx>) {
         let method_name = Symbol::intern(method_name);
         let substs = self.tcx.mk_substs_trait(self_ty, params);
-        for item in self.tcx.associated_items(trait_def_id) {
-            if item.kind == ty::AssociatedKind::Method && item.name == method_name {
-                let method_ty = self.tcx.type_of(item.def_id);
-                let method_ty = method_ty.subst(self.tcx, substs);
-                return (method_ty,
-                        Literal::Value {
-                            value: self.tcx.mk_const(ty::Const {
-                                val: ConstVal::Function(item.def_id, substs),
-                                ty: method_ty
-                            }),
-                        });
+        let method_def_id = match self.trait_methods.get(&(trait_def_id, method_name)).cloned() {
+            Some(method_def_id) => method_def_id,
+            None => {
+                let tcx = self.tcx_at(span);
+                let method_def_id = tcx.associated_item_def_ids(trait_def_id).iter()
+                    .map(|&def_id| tcx.associated_item(def_id))
+                    .find(|item| {
+                        item.kind == ty::AssociatedKind::Method && item.name == method_name
+                    })
+                    .map(|item| item.def_id)
+                    .unwrap_or_else(|| {
+                        self.span_bug(span, &format!("found no method `{}` in `{:?}`",
+                                                     method_name, trait_def_id))
+                    });
+                self.trait_methods.insert((trait_def_id, method_name), method_def_id);
+                method_def_id
             }
-        }
+        };
+
+        let method_ty = self.tcx_at(span).type_of(method_def_id);
+        let method_ty = method_ty.subst(self.tcx, substs);
+        (method_ty,
+         Literal::Value {
+             value: self.tcx.mk_const(ty::Const {
+                 val: ConstVal::Function(method_def_id, substs),
+                 ty: method_ty
+             }),
+         })
+    }
+
+    /// Returns the type and callee of `Deref::deref` (for `MutImmutable`)
+    /// or `DerefMut::deref_mut` (for `MutMutable`) on `base_ty`.
+    pub fn deref_method(&mut self, base_ty: Ty<'tcx>, mutbl: hir::Mutability, span: Span)
+                        -> (Ty<'tcx>, Literal<'tcx>) {
+        let (lang_item, method_name) = match mutbl {
+            hir::MutImmutable => (lang_items::DerefTraitLangItem, "deref"),
+            hir::MutMutable => (lang_items::DerefMutTraitLangItem, "deref_mut"),
+        };
+        let trait_def_id = self.tcx.require_lang_item(lang_item);
+        self.trait_method(trait_def_id, method_name, base_ty, &[], span)
+    }
 
-        bug!("found no method `{}` in `{:?}`", method_name, trait_def_id);
+    /// Returns the type and callee of `<ty as PartialEq>::eq`.
+    pub fn eq_method(&mut self, ty: Ty<'tcx>, span: Span) -> (Ty<'tcx>, Literal<'tcx>) {
+        let eq_def_id = self.tcx.require_lang_item(lang_items::EqTraitLangItem);
+        self.trait_method(eq_def_id, "eq", ty, &[ty], span)
+    }
+
+    /// Returns the type and callee of the method that typeck resolved
+    /// for the method call or overloaded operator `expr`. Unlike
+    /// `trait_method`, this also handles inherent methods.
+    pub fn method_callee(&mut self, expr: &hir::Expr, span: Span) -> (Ty<'tcx>, Literal<'tcx>) {
+        let def_id = match self.tables().type_dependent_defs().get(expr.hir_id) {
+            Some(def) => def.def_id(),
+            None => self.span_bug(span, "method callee was not resolved by typeck"),
+        };
+        let substs = self.tables().node_substs(expr.hir_id);
+        let ty = self.tcx.mk_fn_def(def_id, substs);
+        (ty,
+         Literal::Value {
+             value: self.tcx.mk_const(ty::Const {
+                 val: ConstVal::Function(def_id, substs),
+                 ty
+             }),
+         })
     }
 
     pub fn all_fields(&mut self, adt_def: &ty::AdtDef, variant_index: usize) -> Vec<Field> {
@@ -219,43 +638,251 @@ impl<'a, 'gcx, 'tcx> Cx<'a, 'gcx, 'tcx> {
             .collect()
     }
 
+    /// Returns the index in `adt_def.variants` of the variant `variant_def_id`.
+    pub fn variant_index_of(&self, adt_def: &ty::AdtDef, variant_def_id: DefId) -> usize {
+        adt_def.variant_index_with_id(variant_def_id)
+    }
+
+    /// The integer type of `adt_def`'s discriminant: the `#[repr(iN)]` or
+    /// `#[repr(uN)]` type if there is one, and `isize` otherwise.
+    pub fn discriminant_ty(&self, adt_def: &ty::AdtDef) -> Ty<'tcx> {
+        adt_def.repr.discr_type().to_ty(self.tcx)
+    }
+
+    /// The message reported when the check described by `kind` fails.
+    pub fn assert_message(&mut self, kind: AssertKind<'tcx>) -> AssertMessage<'tcx> {
+        match kind {
+            AssertKind::Overflow(op) => AssertMessage::Math(ConstMathErr::Overflow(op)),
+            AssertKind::DivisionByZero => AssertMessage::Math(ConstMathErr::DivisionByZero),
+            AssertKind::RemainderByZero => AssertMessage::Math(ConstMathErr::RemainderByZero),
+            AssertKind::BoundsCheck { len, index } => AssertMessage::BoundsCheck { len, index },
+        }
+    }
+
     pub fn needs_drop(&mut self, ty: Ty<'tcx>) -> bool {
-        let (ty, param_env) = self.tcx.lift_to_global(&(ty, self.param_env)).unwrap_or_else(|| {
-            bug!("MIR: Cx::needs_drop({:?}, {:?}) got \
-                  type with inference types/regions",
-                 ty, self.param_env);
-        });
-        ty.needs_drop(self.tcx.global_tcx(), param_env)
+        self.needs_drop_many(&[ty])[0]
+    }
+
+    /// Like `needs_drop`, but for several types at once. The
+    /// `param_env` is already global, so only the types themselves
+    /// need to be lifted.
+    pub fn needs_drop_many(&mut self, tys: &[Ty<'tcx>]) -> Vec<bool> {
+        let tcx = self.tcx;
+        let param_env = self.param_env;
+        tys.iter().map(|&ty| {
+            if is_trivially_not_drop(ty) {
+                return false;
+            }
+            let ty = tcx.lift_to_global(&ty).unwrap_or_else(|| {
+                bug!("MIR: Cx::needs_drop({:?}, {:?}) got \
+                      type with inference types/regions",
+                     ty, param_env);
+            });
+            ty.needs_drop(tcx.global_tcx(), param_env)
+        }).collect()
     }
 
     fn lint_level_of(&self, node_id: ast::NodeId) -> LintLevel {
         let hir_id = self.tcx.hir.definitions().node_to_hir_id(node_id);
-        let has_lint_level = self.tcx.dep_graph.with_ignore(|| {
-            self.tcx.lint_levels(LOCAL_CRATE).lint_level_set(hir_id).is_some()
-        });
-
-        if has_lint_level {
+        if self.lint_levels.lint_level_set(hir_id).is_some() {
             LintLevel::Explicit(node_id)
         } else {
             LintLevel::Inherited
         }
     }
 
+    /// Runs `f` with `param_env` in place of the body's own, for lowering
+    /// a nested constant that is checked in its own environment. Helpers
+    /// like `needs_drop` and `eval_const_arg` called inside all see
+    /// `param_env`.
+    pub fn with_param_env<F, R>(&mut self, param_env: ty::ParamEnv<'gcx>, f: F) -> R
+        where F: FnOnce(&mut Self) -> R
+    {
+        let old_param_env = mem::replace(&mut self.param_env, param_env);
+        let result = f(self);
+        self.param_env = old_param_env;
+        result
+    }
+
     pub fn tcx(&self) -> TyCtxt<'a, 'gcx, 'tcx> {
         self.tcx
     }
 
+    /// Returns the `tcx` with `span` attached, so that queries (and any
+    /// cycle errors they hit) are attributed to the code being lowered.
+    pub fn tcx_at(&self, span: Span) -> TyCtxtAt<'a, 'gcx, 'tcx> {
+        self.tcx.at(span)
+    }
+
+    /// The crate's enabled `#![feature(...)]` gates. Reading these is
+    /// not tracked by incremental compilation, so only use them to pick
+    /// between lowerings that earlier (tracked) passes already accepted.
+    pub fn features(&self) -> Ref<'a, feature_gate::Features> {
+        self.tcx.sess.features.borrow()
+    }
+
+    pub fn span_of(&self, node_id: ast::NodeId) -> Span {
+        self.tcx.hir.span(node_id)
+    }
+
     pub fn tables(&self) -> &'a ty::TypeckTables<'gcx> {
         self.tables
     }
 
+    /// `param_env`, but with `Reveal::All`, so that projections are
+    /// normalized even through specializable impls. This is only sound
+    /// where the result can't be changed by further specialization, i.e.
+    /// when the types involved are fully monomorphic; otherwise use
+    /// `param_env`.
+    pub fn param_env_reveal_all(&self) -> ty::ParamEnv<'gcx> {
+        self.param_env.reveal_all()
+    }
+
+    /// The adjustments typeck recorded for `expr`, in the order they
+    /// apply; empty if there are none.
+    pub fn adjustments(&self, expr: &hir::Expr) -> &'a [ty::adjustment::Adjustment<'tcx>] {
+        self.tables.expr_adjustments(expr)
+    }
+
+    pub fn constness(&self) -> hir::Constness {
+        self.constness
+    }
+
+    /// True if this is the body of a function or closure, `const fn`
+    /// included.
+    pub fn is_fn_body(&self) -> bool {
+        match self.body_owner_kind {
+            hir::BodyOwnerKind::Fn => true,
+            hir::BodyOwnerKind::Const |
+            hir::BodyOwnerKind::Static(_) => false,
+        }
+    }
+
+    /// Returns the destruction scope of `scope`, if it has one. Only
+    /// node scopes can have destruction scopes.
+    pub fn destruction_scope(&self, scope: region::Scope) -> Option<region::Scope> {
+        match scope.data() {
+            region::ScopeData::Node(id) => self.scope_tree().opt_destruction_scope(id),
+            _ => None,
+        }
+    }
+
+    pub fn is_generator(&self) -> bool {
+        self.is_generator
+    }
+
+    /// The scope of the rest of block `block_id` after its statement
+    /// `index`, i.e. the scope of the bindings introduced by a `let` there.
+    pub fn statement_scope(&self, block_id: hir::ItemLocalId, index: usize) -> region::Scope {
+        region::Scope::Remainder(region::BlockRemainder {
+            block: block_id,
+            first_statement_index: region::FirstStatementIndex::new(index),
+        })
+    }
+
+    pub fn scope_tree(&self) -> &region::ScopeTree {
+        &self.region_scope_tree
+    }
+
     pub fn check_overflow(&self) -> bool {
         self.check_overflow
     }
 
+    /// Returns how the closure `closure_def_id` captures `upvar_id`, or
+    /// `None` if that is not one of the closure's upvars.
+    pub fn upvar_capture(&self,
+                         closure_def_id: DefId,
+                         upvar_id: ty::UpvarId)
+                         -> Option<ty::UpvarCapture<'tcx>> {
+        if upvar_id.closure_expr_id.to_def_id() != closure_def_id {
+            return None;
+        }
+        self.tables().upvar_capture_map.get(&upvar_id).cloned()
+    }
+
+    /// The variables captured by the closure or generator `closure_def_id`,
+    /// which must be defined in the body being lowered, in the order of
+    /// the fields of its environment.
+    pub fn closure_captures(&self, closure_def_id: DefId) -> Vec<UpvarInfo<'tcx>> {
+        let closure_id = self.tcx.hir.as_local_node_id(closure_def_id).unwrap_or_else(|| {
+            bug!("closure_captures: {:?} is not a local closure", closure_def_id)
+        });
+        let closure_span = self.tcx.hir.span(closure_id);
+        let closure_ty = self.tables().node_id_to_type(self.tcx.hir.node_to_hir_id(closure_id));
+        let substs = match closure_ty.sty {
+            ty::TyClosure(_, substs) |
+            ty::TyGenerator(_, substs, _) => substs,
+            _ => {
+                self.span_bug(closure_span,
+                              &format!("closure w/o closure type: {:?}", closure_ty))
+            }
+        };
+        self.tcx.with_freevars(closure_id, |freevars| {
+            freevars.iter()
+                .zip(substs.upvar_tys(closure_def_id, self.tcx))
+                .map(|(freevar, ty)| {
+                    let upvar_id = ty::UpvarId {
+                        var_id: self.tcx.hir.node_to_hir_id(freevar.var_id()),
+                        closure_expr_id: closure_def_id.to_local(),
+                    };
+                    let capture = self.upvar_capture(closure_def_id, upvar_id)
+                        .unwrap_or_else(|| {
+                            self.span_bug(closure_span,
+                                          &format!("no capture recorded for upvar {:?}",
+                                                   upvar_id))
+                        });
+                    UpvarInfo { upvar_id, capture, ty }
+                })
+                .collect()
+        })
+    }
+
+    /// The type of a pointer to the fn or method `def_id` instantiated
+    /// with `substs`, i.e. what reifying the fn item gives. Late-bound
+    /// regions are kept; associated types are normalized, as typeck does
+    /// before it records the type of the coercion.
+    pub fn fn_ptr_ty(&self, def_id: DefId, substs: &'tcx Substs<'tcx>) -> Ty<'tcx> {
+        let sig = self.tcx.fn_sig(def_id).subst(self.tcx, substs);
+        self.tcx.mk_fn_ptr(self.normalize(&sig))
+    }
+
+    /// Normalizes the associated types in `value` against `param_env`.
+    /// Typeck has already proven everything we lower, so failing to
+    /// normalize here is a bug.
+    pub fn normalize<T>(&self, value: &T) -> T
+        where T: TypeFoldable<'tcx>
+    {
+        let cause = traits::ObligationCause::dummy();
+        traits::fully_normalize(self.infcx, cause, self.param_env, value).unwrap_or_else(|errors| {
+            bug!("MIR: Cx::normalize({:?}) failed: {:?}", value, errors)
+        })
+    }
+
     pub fn type_moves_by_default(&self, ty: Ty<'tcx>, span: Span) -> bool {
         self.infcx.type_moves_by_default(self.param_env, ty, span)
     }
+
+    /// True if `ty` is `Copy`, i.e. reading a place of type `ty` copies
+    /// rather than moves it. This is just `!type_moves_by_default`.
+    pub fn type_is_copy(&self, ty: Ty<'tcx>, span: Span) -> bool {
+        !self.type_moves_by_default(ty, span)
+    }
+}
+
+/// True for types that obviously never need dropping, which lets
+/// `Cx::needs_drop` skip the lift and the query for them.
+fn is_trivially_not_drop(ty: Ty) -> bool {
+    match ty.sty {
+        ty::TyBool |
+        ty::TyChar |
+        ty::TyInt(_) |
+        ty::TyUint(_) |
+        ty::TyFloat(_) |
+        ty::TyRawPtr(_) |
+        ty::TyRef(..) |
+        ty::TyNever => true,
+        _ => false,
+    }
 }
 
 fn lint_level_for_hir_id(tcx: TyCtxt, mut id: ast::NodeId) -> ast::NodeId {
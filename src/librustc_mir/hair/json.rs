@@ -0,0 +1,201 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! `-Z emit-hair=json`: writes the HAIR of each body into the output
+//! directory, for tools that want to inspect it without redoing the
+//! lowering. The HAIR of the item `foo::bar` goes to `foo-bar.hair.json`.
+//!
+//! Every node is encoded as an object with the fields, in this order:
+//!
+//! - `node`: `"expr"`, `"block"` or `"stmt"`;
+//! - `kind`: the `ExprKind` or `StmtKind` variant (`"Block"` for blocks);
+//! - `ty`: the type of an expression, `null` for blocks and statements;
+//! - `span`: the source span, as printed in diagnostics; for a `let` this
+//!   is the span of its pattern;
+//! - `children`: the nested expressions, blocks and statements, in
+//!   evaluation order.
+//!
+//! Patterns are not encoded, so a `match` lists its scrutinee followed by
+//! the guard (if any) and body of each arm.
+//!
+//! The body is mirrored a second time for this, after the builder is done
+//! with it, so with `-Z dump-hair` also set its nodes are printed twice.
+
+use hair::*;
+use hair::cx::Cx;
+use rustc::hir;
+use rustc::hir::def_id::LOCAL_CRATE;
+use rustc::infer::InferCtxt;
+use rustc_serialize::json;
+use std::fs::File;
+use std::io::Write;
+use syntax::ast;
+use syntax_pos::Span;
+
+#[derive(RustcEncodable)]
+struct Node {
+    node: &'static str,
+    kind: &'static str,
+    ty: Option<String>,
+    span: String,
+    children: Vec<Node>,
+}
+
+/// Mirrors the body `body_id` of `src_id` with a fresh `Cx` and writes it
+/// to the output directory. Bodies whose tables are tainted by errors are
+/// skipped, as the builder doesn't lower them either.
+pub fn emit_hair<'a, 'gcx, 'tcx>(infcx: &'a InferCtxt<'a, 'gcx, 'tcx>,
+                                 src_id: ast::NodeId,
+                                 body_id: hir::BodyId) {
+    let tcx = infcx.tcx;
+    let mut cx = Cx::new(infcx, src_id);
+    if cx.tables().tainted_by_errors {
+        return;
+    }
+
+    let expr = cx.mirror_expr(&tcx.hir.body(body_id).value);
+    let node = expr_node(&mut cx, expr);
+
+    let item_name = tcx.hir.def_path(tcx.hir.local_def_id(src_id))
+        .to_filename_friendly_no_crate();
+    let path = tcx.output_filenames(LOCAL_CRATE).out_directory
+        .join(format!("{}.hair.json", item_name));
+    let result = File::create(&path).and_then(|mut file| {
+        writeln!(file, "{}", json::as_pretty_json(&node))
+    });
+    if let Err(err) = result {
+        tcx.sess.err(&format!("could not write HAIR to `{}`: {}", path.display(), err));
+    }
+}
+
+fn span_string(cx: &Cx, span: Span) -> String {
+    cx.tcx().sess.codemap().span_to_string(span)
+}
+
+fn expr_node<'a, 'gcx, 'tcx>(cx: &mut Cx<'a, 'gcx, 'tcx>, expr: Expr<'tcx>) -> Node {
+    let ty = Some(expr.ty.to_string());
+    let span = span_string(cx, expr.span);
+    let (kind, exprs) = match expr.kind {
+        ExprKind::Block { body } => {
+            let block = cx.mirror_block(body);
+            return Node {
+                node: "expr",
+                kind: "Block",
+                ty,
+                span,
+                children: vec![block_node(cx, block)],
+            };
+        }
+        ExprKind::Scope { value, .. } => ("Scope", vec![value]),
+        ExprKind::Box { value } => ("Box", vec![value]),
+        ExprKind::Call { fun, args, .. } => {
+            ("Call", Some(fun).into_iter().chain(args).collect())
+        }
+        ExprKind::Deref { arg } => ("Deref", vec![arg]),
+        ExprKind::Binary { lhs, rhs, .. } => ("Binary", vec![lhs, rhs]),
+        ExprKind::LogicalOp { lhs, rhs, .. } => ("LogicalOp", vec![lhs, rhs]),
+        ExprKind::Unary { arg, .. } => ("Unary", vec![arg]),
+        ExprKind::Cast { source } => ("Cast", vec![source]),
+        ExprKind::Use { source } => ("Use", vec![source]),
+        ExprKind::NeverToAny { source } => ("NeverToAny", vec![source]),
+        ExprKind::ReifyFnPointer { source } => ("ReifyFnPointer", vec![source]),
+        ExprKind::ClosureFnPointer { source } => ("ClosureFnPointer", vec![source]),
+        ExprKind::UnsafeFnPointer { source } => ("UnsafeFnPointer", vec![source]),
+        ExprKind::Unsize { source } => ("Unsize", vec![source]),
+        ExprKind::If { condition, then, otherwise } => {
+            ("If", vec![condition, then].into_iter().chain(otherwise).collect())
+        }
+        ExprKind::Loop { condition, body } => {
+            ("Loop", condition.into_iter().chain(Some(body)).collect())
+        }
+        ExprKind::Match { discriminant, arms } => {
+            let mut exprs = vec![discriminant];
+            for arm in arms {
+                exprs.extend(arm.guard);
+                exprs.push(arm.body);
+            }
+            ("Match", exprs)
+        }
+        ExprKind::Assign { lhs, rhs } => ("Assign", vec![lhs, rhs]),
+        ExprKind::AssignOp { lhs, rhs, .. } => ("AssignOp", vec![lhs, rhs]),
+        ExprKind::Field { lhs, .. } => ("Field", vec![lhs]),
+        ExprKind::Index { lhs, index } => ("Index", vec![lhs, index]),
+        ExprKind::VarRef { .. } => ("VarRef", vec![]),
+        ExprKind::SelfRef => ("SelfRef", vec![]),
+        ExprKind::StaticRef { .. } => ("StaticRef", vec![]),
+        ExprKind::Borrow { arg, .. } => ("Borrow", vec![arg]),
+        ExprKind::Break { value, .. } => ("Break", value.into_iter().collect()),
+        ExprKind::Continue { .. } => ("Continue", vec![]),
+        ExprKind::Return { value } => ("Return", value.into_iter().collect()),
+        ExprKind::Repeat { value, .. } => ("Repeat", vec![value]),
+        ExprKind::Array { fields } => ("Array", fields),
+        ExprKind::Tuple { fields } => ("Tuple", fields),
+        ExprKind::Adt { fields, base, .. } => {
+            ("Adt", fields.into_iter()
+                          .map(|field| field.expr)
+                          .chain(base.map(|base| base.base))
+                          .collect())
+        }
+        ExprKind::Closure { upvars, .. } => ("Closure", upvars),
+        ExprKind::Literal { .. } => ("Literal", vec![]),
+        ExprKind::InlineAsm { outputs, inputs, .. } => {
+            ("InlineAsm", outputs.into_iter().chain(inputs).collect())
+        }
+        ExprKind::Yield { value } => ("Yield", vec![value]),
+    };
+    let children = exprs.into_iter().map(|expr| {
+        let expr = cx.mirror(expr);
+        expr_node(cx, expr)
+    }).collect();
+    Node { node: "expr", kind, ty, span, children }
+}
+
+fn block_node<'a, 'gcx, 'tcx>(cx: &mut Cx<'a, 'gcx, 'tcx>, block: Block<'tcx>) -> Node {
+    let mut children = vec![];
+    for stmt in block.stmts {
+        let stmt = cx.mirror(stmt);
+        children.push(stmt_node(cx, stmt));
+    }
+    if let Some(expr) = block.expr {
+        let expr = cx.mirror(expr);
+        children.push(expr_node(cx, expr));
+    }
+    Node {
+        node: "block",
+        kind: "Block",
+        ty: None,
+        span: span_string(cx, block.span),
+        children,
+    }
+}
+
+fn stmt_node<'a, 'gcx, 'tcx>(cx: &mut Cx<'a, 'gcx, 'tcx>, stmt: Stmt<'tcx>) -> Node {
+    let (kind, span, children) = match stmt.kind {
+        StmtKind::Expr { expr, .. } => {
+            let expr = cx.mirror(expr);
+            let span = expr.span;
+            ("Expr", span, vec![expr_node(cx, expr)])
+        }
+        StmtKind::Let { pattern, initializer, .. } => {
+            let children = initializer.into_iter().map(|init| {
+                let init = cx.mirror(init);
+                expr_node(cx, init)
+            }).collect();
+            ("Let", pattern.span, children)
+        }
+    };
+    Node {
+        node: "stmt",
+        kind,
+        ty: None,
+        span: span_string(cx, span),
+        children,
+    }
+}
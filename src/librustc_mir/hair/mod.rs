@@ -14,18 +14,19 @@
 //! unit-tested and separated from the Rust source and compiler data
 //! structures.
 
-use rustc_const_math::ConstUsize;
-use rustc::mir::{BinOp, BorrowKind, Field, Literal, UnOp};
+use rustc_const_math::{ConstUsize, Op};
+use rustc::mir::{BinOp, BorrowKind, Field, Literal, Operand, UnOp};
 use rustc::hir::def_id::DefId;
 use rustc::middle::region;
 use rustc::ty::subst::Substs;
-use rustc::ty::{AdtDef, ClosureSubsts, Region, Ty, GeneratorInterior};
+use rustc::ty::{AdtDef, ClosureSubsts, Region, Ty, GeneratorInterior, UpvarCapture, UpvarId};
 use rustc::hir;
 use syntax::ast;
 use syntax_pos::Span;
 use self::cx::Cx;
 
 pub mod cx;
+pub mod json;
 
 pub use rustc_const_eval::pattern::{BindingMode, Pattern, PatternKind, FieldPattern};
 
@@ -44,6 +45,31 @@ impl LintLevel {
     }
 }
 
+/// The runtime checks the builder emits `Assert` terminators for; see
+/// `Cx::assert_message`.
+#[derive(Clone, Debug)]
+pub enum AssertKind<'tcx> {
+    /// Arithmetic overflow in `op`.
+    Overflow(Op),
+    DivisionByZero,
+    RemainderByZero,
+    /// Indexing out of bounds, reporting `len` and `index`.
+    BoundsCheck {
+        len: Operand<'tcx>,
+        index: Operand<'tcx>,
+    },
+}
+
+/// One variable captured by a closure; see `Cx::closure_captures`.
+#[derive(Copy, Clone, Debug)]
+pub struct UpvarInfo<'tcx> {
+    pub upvar_id: UpvarId,
+    pub capture: UpvarCapture<'tcx>,
+    /// The type of the closure's environment field for this capture,
+    /// i.e. `&T` or `&mut T` for a by-reference capture of a `T`.
+    pub ty: Ty<'tcx>,
+}
+
 #[derive(Clone, Debug)]
 pub struct Block<'tcx> {
     pub targeted_by_break: bool,
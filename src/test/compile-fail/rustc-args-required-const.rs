@@ -0,0 +1,24 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(rustc_attrs)]
+
+#[rustc_args_required_const(1)]
+fn shuffle(_a: i32, _imm: i32) {}
+
+const IMM: i32 = 2;
+
+fn main() {
+    let imm = 3;
+    shuffle(imm, 1);
+    shuffle(imm, IMM + 1);
+    shuffle(1, imm); //~ ERROR argument 2 is required to be a constant
+    //~| NOTE non-constant path in constant expression
+}
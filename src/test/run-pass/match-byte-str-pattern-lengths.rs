@@ -0,0 +1,34 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Byte string patterns are compared as `&[u8; N]` constants unsized to
+// `&[u8]`; check empty and non-empty patterns, against both slices and
+// array references.
+
+fn classify(buf: &[u8]) -> u32 {
+    match buf {
+        b"" => 0,
+        b"abcd" => 4,
+        _ => 1,
+    }
+}
+
+fn main() {
+    assert_eq!(classify(b""), 0);
+    assert_eq!(classify(b"abcd"), 4);
+    assert_eq!(classify(b"abc"), 1);
+    assert_eq!(classify(b"abcde"), 1);
+
+    let four: &[u8; 4] = b"abcd";
+    match four {
+        b"abcd" => {}
+        _ => unreachable!(),
+    }
+}
@@ -0,0 +1,39 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that every kind of literal, negated or not, is lowered to the
+// same value constant evaluation would produce.
+
+#![feature(i128_type)]
+
+#[allow(overflowing_literals)]
+fn ints() -> (i8, i8, u8, i16, i32, i64, i128, isize, u128, usize, i8) {
+    (-128, 127, 255, -32768, -1, -9223372036854775808, -170141183460469231731687303715884105728,
+     -1, 340282366920938463463374607431768211455, 3, 200)
+}
+
+fn floats() -> (f32, f64, f32, f64) {
+    (1.5f32, -2.25, -0.0, 1e300)
+}
+
+fn others() -> (bool, bool, char, u8, &'static str, &'static [u8; 3]) {
+    (true, false, 'x', b'y', "hello", b"abc")
+}
+
+fn main() {
+    assert_eq!(ints(), (-128, 127, 255, -32768, -1, i64::min_value(), i128::min_value(),
+                        -1, u128::max_value(), 3, -56));
+    let (a, b, c, d) = floats();
+    assert_eq!(a, 1.5);
+    assert_eq!(b, -2.25);
+    assert_eq!(c.to_bits(), 0x8000_0000);
+    assert_eq!(d, 1e300);
+    assert_eq!(others(), (true, false, 'x', 121, "hello", &[97, 98, 99]));
+}
@@ -0,0 +1,37 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that reifying fn items gives pointers of the expected type,
+// including when the signature has a projection that normalizes away.
+
+use std::vec;
+
+fn first<I: Iterator>(item: I::Item) -> Option<I::Item> {
+    Some(item)
+}
+
+extern "C" fn add_one(x: u32) -> u32 {
+    x + 1
+}
+
+fn pick<'a>(x: &'a u8, _: &u8) -> &'a u8 {
+    x
+}
+
+fn main() {
+    let f: fn(u8) -> Option<u8> = first::<vec::IntoIter<u8>>;
+    assert_eq!(f(7), Some(7));
+
+    let g: extern "C" fn(u32) -> u32 = add_one;
+    assert_eq!(g(1), 2);
+
+    let h: for<'a, 'b> fn(&'a u8, &'b u8) -> &'a u8 = pick;
+    assert_eq!(*h(&1, &2), 1);
+}
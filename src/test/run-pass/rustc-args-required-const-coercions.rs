@@ -0,0 +1,33 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that arguments lowered to constants because of
+// `#[rustc_args_required_const]` keep the coercions of the argument.
+
+#![feature(rustc_attrs)]
+
+#[rustc_args_required_const(1)]
+fn call(x: u8, f: fn(u8) -> u8) -> u8 {
+    f(x)
+}
+
+#[rustc_args_required_const(0)]
+fn len(bytes: &[u8]) -> usize {
+    bytes.len()
+}
+
+fn double(x: u8) -> u8 {
+    x * 2
+}
+
+fn main() {
+    assert_eq!(call(3, double), 6);
+    assert_eq!(len(b"abc"), 3);
+}